@@ -3,12 +3,85 @@
 
 //! Types used when performing HTTP requests.
 
+use std::time::Duration;
+
+use async_trait::async_trait;
+use base64::engine::{general_purpose::STANDARD, Engine as _};
 use custom_debug_derive::Debug;
+use ed25519_dalek::{Signer, SigningKey, Verifier, VerifyingKey};
+#[cfg(with_reqwest)]
+use futures::StreamExt as _;
 use linera_witty::{WitLoad, WitStore, WitType};
+use rand::Rng;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+use thiserror::Error;
 
 use crate::hex_debug;
 
+/// The headers covered by the HTTP signature, in the fixed order they must be signed in.
+const SIGNED_HEADERS: [&str; 4] = ["(request-target)", "host", "date", "digest"];
+
+/// The headers covered by the HTTP signature on a [`Response`], in the fixed order they must be
+/// signed in. A response has no method or URL, so `(status)` stands in for `(request-target)`.
+const RESPONSE_SIGNED_HEADERS: [&str; 3] = ["(status)", "date", "digest"];
+
+/// An error that can occur while building or sending an HTTP [`Request`].
+#[derive(Debug, Error)]
+pub enum HttpError {
+    /// The provided URL could not be parsed.
+    #[error("invalid URL: {0}")]
+    InvalidUrl(#[from] url::ParseError),
+
+    /// The request body could not be serialized as JSON.
+    #[error("failed to serialize request body as JSON: {0}")]
+    InvalidJsonBody(#[from] serde_json::Error),
+
+    /// The underlying `reqwest` client returned an error.
+    #[cfg(with_reqwest)]
+    #[error(transparent)]
+    Reqwest(#[from] reqwest::Error),
+
+    /// A header required to build the HTTP signature was missing.
+    #[error("missing `{0}` header required to sign or verify the request")]
+    MissingSignatureHeader(String),
+
+    /// The `Signature` header was malformed.
+    #[error("malformed `Signature` header")]
+    MalformedSignature,
+
+    /// The signature did not match the signing string.
+    #[error("HTTP signature verification failed: {0}")]
+    InvalidSignature(#[from] ed25519_dalek::SignatureError),
+
+    /// The `Digest` header did not match the digest of the body.
+    #[error("`Digest` header does not match the digest of the body")]
+    DigestMismatch,
+
+    /// The response body exceeded the configured `max_body_size`.
+    #[error("response body exceeds the maximum allowed size of {0} bytes")]
+    BodyTooLarge(usize),
+
+    /// A header name or value could not be converted to the underlying HTTP library's type.
+    #[cfg(with_reqwest)]
+    #[error("invalid header: {0}")]
+    InvalidHeader(String),
+}
+
+impl HttpError {
+    /// Returns whether this is a transient failure worth retrying, i.e. a connection-level
+    /// error from the transport. Every other variant (an invalid URL, a malformed signature, a
+    /// digest mismatch, ...) is deterministic, so retrying it would only waste time and never
+    /// succeed.
+    fn is_retryable(&self) -> bool {
+        match self {
+            #[cfg(with_reqwest)]
+            HttpError::Reqwest(error) => error.is_connect() || error.is_timeout(),
+            _ => false,
+        }
+    }
+}
+
 /// The method used in an HTTP request.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, WitLoad, WitStore, WitType)]
 pub enum Method {
@@ -77,11 +150,7 @@ impl Response {
     /// Creates a [`Response`] from a [`reqwest::Response`], waiting for it to be fully
     /// received.
     pub async fn from_reqwest(response: reqwest::Response) -> reqwest::Result<Self> {
-        let headers = response
-            .headers()
-            .into_iter()
-            .map(|(name, value)| (name.to_string(), value.as_bytes().to_owned()))
-            .collect();
+        let headers = Self::headers_from_reqwest(&response);
 
         Ok(Response {
             status: response.status().as_u16(),
@@ -89,6 +158,135 @@ impl Response {
             body: response.bytes().await?.to_vec(),
         })
     }
+
+    /// Creates a [`Response`] from a [`reqwest::Response`], like [`Response::from_reqwest`], but
+    /// rejecting the response instead of buffering more than `max_body_size` bytes. This guards
+    /// against OOM-ing a Wasm instance when a server sends an unbounded response.
+    pub async fn from_reqwest_with_limit(
+        response: reqwest::Response,
+        max_body_size: usize,
+    ) -> Result<Self, HttpError> {
+        if response.content_length().is_some_and(|length| length as usize > max_body_size) {
+            return Err(HttpError::BodyTooLarge(max_body_size));
+        }
+
+        let status = response.status().as_u16();
+        let headers = Self::headers_from_reqwest(&response);
+        let mut body = Vec::new();
+        let mut stream = response.bytes_stream();
+
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            if body.len() + chunk.len() > max_body_size {
+                return Err(HttpError::BodyTooLarge(max_body_size));
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        Ok(Response {
+            status,
+            headers,
+            body,
+        })
+    }
+
+    fn headers_from_reqwest(response: &reqwest::Response) -> Vec<(String, Vec<u8>)> {
+        response
+            .headers()
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value.as_bytes().to_owned()))
+            .collect()
+    }
+}
+
+impl Response {
+    /// Adds a `Digest` header computed as `SHA-256=` followed by the base64-encoded SHA-256
+    /// digest of the body.
+    pub fn with_digest(mut self) -> Self {
+        let digest = format!("SHA-256={}", STANDARD.encode(Sha256::digest(&self.body)));
+        self.headers.push(("digest".to_string(), digest.into_bytes()));
+        self
+    }
+
+    /// Signs this response with `key`, first attaching a `Digest` header so that the signature
+    /// also covers the body, then a `Signature` header covering [`RESPONSE_SIGNED_HEADERS`].
+    ///
+    /// A response has no method or URL to sign a `(request-target)` line for, so, unlike
+    /// [`Request::signed`], the synthetic pseudo-header here is `(status)`, set to the response's
+    /// status code. The caller must have already set a `date` header.
+    pub fn signed(self, key: &SigningKey, key_id: &str) -> Result<Self, HttpError> {
+        let mut response = self.with_digest();
+        let signing_string = response.signing_string()?;
+        let signature = key.sign(signing_string.as_bytes());
+        let header = format!(
+            "keyId=\"{key_id}\",algorithm=\"ed25519\",headers=\"{}\",signature=\"{}\"",
+            RESPONSE_SIGNED_HEADERS.join(" "),
+            STANDARD.encode(signature.to_bytes()),
+        );
+        response.headers.push(("signature".to_string(), header.into_bytes()));
+        Ok(response)
+    }
+
+    /// Verifies that this response carries a valid `Signature` header for `public_key`.
+    ///
+    /// As with [`Request::verify_signature`], the `Digest` header is checked against the body
+    /// first, so a tampered body is rejected outright rather than accepted because a stale
+    /// signature still matches the (unsigned) header value.
+    pub fn verify_signature(&self, public_key: &VerifyingKey) -> Result<(), HttpError> {
+        let digest = self.header_value("digest")?;
+        if digest != format!("SHA-256={}", STANDARD.encode(Sha256::digest(&self.body))) {
+            return Err(HttpError::DigestMismatch);
+        }
+
+        let signature_header = self.header_value("signature")?;
+        let signature = parse_signature(&signature_header)?;
+        let signing_string = self.signing_string()?;
+        public_key.verify(signing_string.as_bytes(), &signature)?;
+        Ok(())
+    }
+
+    /// Builds the signing string: a `(status)` pseudo-header line followed by one `name: value`
+    /// line per remaining entry of [`RESPONSE_SIGNED_HEADERS`], joined by `\n`.
+    fn signing_string(&self) -> Result<String, HttpError> {
+        let mut lines = vec![format!("(status): {}", self.status)];
+        for name in &RESPONSE_SIGNED_HEADERS[1..] {
+            lines.push(format!("{name}: {}", self.header_value(name)?));
+        }
+        Ok(lines.join("\n"))
+    }
+
+    /// Returns the value of the first header named `name` (case-insensitively), as a string.
+    fn header_value(&self, name: &str) -> Result<String, HttpError> {
+        self.headers
+            .iter()
+            .find(|(header_name, _)| header_name.eq_ignore_ascii_case(name))
+            .map(|(_, value)| String::from_utf8_lossy(value).into_owned())
+            .ok_or_else(|| HttpError::MissingSignatureHeader(name.to_string()))
+    }
+}
+
+/// A stream of body chunks for an HTTP [`Response`], for callers that want to process a large
+/// body incrementally instead of buffering it whole.
+#[cfg(with_reqwest)]
+pub struct ResponseStream {
+    inner: reqwest::Response,
+}
+
+#[cfg(with_reqwest)]
+impl ResponseStream {
+    /// Wraps `response` so its body can be read incrementally.
+    pub fn new(response: reqwest::Response) -> Self {
+        ResponseStream { inner: response }
+    }
+
+    /// Returns the next chunk of the body, or `None` once the body is fully consumed.
+    pub async fn next_chunk(&mut self) -> Option<Result<Vec<u8>, HttpError>> {
+        match self.inner.chunk().await {
+            Ok(Some(bytes)) => Some(Ok(bytes.to_vec())),
+            Ok(None) => None,
+            Err(error) => Some(Err(error.into())),
+        }
+    }
 }
 
 /// A header for a HTTP request or response.
@@ -111,4 +309,698 @@ impl Header {
             value: value.into(),
         }
     }
+}
+
+/// A trait for types that can be converted into a validated URL for a [`Request`].
+///
+/// This mirrors `reqwest`'s own `IntoUrl` trait, letting callers pass a `&str`, `String`, or an
+/// already-parsed [`url::Url`] while ensuring the URL is validated as soon as the request is
+/// built, rather than only once it is sent.
+pub trait IntoUrl {
+    /// Validates `self` and converts it into a URL string.
+    fn into_url(self) -> Result<String, HttpError>;
+}
+
+impl IntoUrl for &str {
+    fn into_url(self) -> Result<String, HttpError> {
+        Ok(url::Url::parse(self)?.to_string())
+    }
+}
+
+impl IntoUrl for String {
+    fn into_url(self) -> Result<String, HttpError> {
+        self.as_str().into_url()
+    }
+}
+
+impl IntoUrl for url::Url {
+    fn into_url(self) -> Result<String, HttpError> {
+        Ok(self.to_string())
+    }
+}
+
+/// An outbound HTTP request.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Request {
+    /// The method used for the request.
+    pub method: Method,
+
+    /// The URL the request is sent to.
+    pub url: String,
+
+    /// The headers included in the request.
+    pub headers: Vec<Header>,
+
+    /// The body of the request.
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// Creates a new [`Request`] with the given `method`, targeting `url`.
+    fn new(method: Method, url: impl IntoUrl) -> Result<Self, HttpError> {
+        Ok(Request {
+            method,
+            url: url.into_url()?,
+            headers: Vec::new(),
+            body: Vec::new(),
+        })
+    }
+
+    /// Creates a new GET [`Request`] targeting `url`.
+    pub fn get(url: impl IntoUrl) -> Result<Self, HttpError> {
+        Self::new(Method::Get, url)
+    }
+
+    /// Creates a new POST [`Request`] targeting `url`.
+    pub fn post(url: impl IntoUrl) -> Result<Self, HttpError> {
+        Self::new(Method::Post, url)
+    }
+
+    /// Creates a new PUT [`Request`] targeting `url`.
+    pub fn put(url: impl IntoUrl) -> Result<Self, HttpError> {
+        Self::new(Method::Put, url)
+    }
+
+    /// Creates a new DELETE [`Request`] targeting `url`.
+    pub fn delete(url: impl IntoUrl) -> Result<Self, HttpError> {
+        Self::new(Method::Delete, url)
+    }
+
+    /// Adds a header to this [`Request`].
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<Vec<u8>>) -> Self {
+        self.headers.push(Header::new(name, value));
+        self
+    }
+
+    /// Sets the body of this [`Request`] to the raw bytes in `body`.
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// Sets the body of this [`Request`] to `value`, serialized as JSON, and adds the
+    /// corresponding `content-type` header.
+    pub fn json<T: Serialize>(self, value: &T) -> Result<Self, HttpError> {
+        let body = serde_json::to_vec(value)?;
+        Ok(self.body(body).header("content-type", "application/json"))
+    }
+
+    /// Adds a `Digest` header computed as `SHA-256=` followed by the base64-encoded SHA-256
+    /// digest of the body.
+    pub fn with_digest(self) -> Self {
+        let digest = format!("SHA-256={}", STANDARD.encode(Sha256::digest(&self.body)));
+        self.header("digest", digest)
+    }
+
+    /// Signs this request with `key`, first attaching a `Digest` header so that the signature
+    /// also covers the body, then a `Signature` header covering `(request-target)`, `host`,
+    /// `date`, and `digest`, in that order.
+    ///
+    /// The caller must have already set `host` and `date` headers.
+    pub fn signed(self, key: &SigningKey, key_id: &str) -> Result<Self, HttpError> {
+        let request = self.with_digest();
+        let signing_string = request.signing_string()?;
+        let signature = key.sign(signing_string.as_bytes());
+        let header = format!(
+            "keyId=\"{key_id}\",algorithm=\"ed25519\",headers=\"{}\",signature=\"{}\"",
+            SIGNED_HEADERS.join(" "),
+            STANDARD.encode(signature.to_bytes()),
+        );
+        Ok(request.header("signature", header))
+    }
+
+    /// Verifies that this request carries a valid `Signature` header for `public_key`.
+    ///
+    /// The `Digest` header is checked against the body first, so that a tampered body is
+    /// rejected outright instead of being accepted because a stale signature still matches the
+    /// (unsigned) header value.
+    pub fn verify_signature(&self, public_key: &VerifyingKey) -> Result<(), HttpError> {
+        let digest = self.header_value("digest")?;
+        if digest != format!("SHA-256={}", STANDARD.encode(Sha256::digest(&self.body))) {
+            return Err(HttpError::DigestMismatch);
+        }
+
+        let signature_header = self.header_value("signature")?;
+        let signature = parse_signature(&signature_header)?;
+        let signing_string = self.signing_string()?;
+        public_key.verify(signing_string.as_bytes(), &signature)?;
+        Ok(())
+    }
+
+    /// Builds the signing string: one `name: value` line per entry of [`SIGNED_HEADERS`],
+    /// joined by `\n`, with the synthetic `(request-target)` line set to the lowercase method
+    /// and the URL path.
+    fn signing_string(&self) -> Result<String, HttpError> {
+        let url = url::Url::parse(&self.url)?;
+        let method = format!("{:?}", self.method).to_lowercase();
+        let request_target = format!("(request-target): {method} {}", url.path());
+
+        let mut lines = vec![request_target];
+        for name in &SIGNED_HEADERS[1..] {
+            lines.push(format!("{name}: {}", self.header_value(name)?));
+        }
+        Ok(lines.join("\n"))
+    }
+
+    /// Returns the value of the first header named `name` (case-insensitively), as a string.
+    fn header_value(&self, name: &str) -> Result<String, HttpError> {
+        self.headers
+            .iter()
+            .find(|header| header.name.eq_ignore_ascii_case(name))
+            .map(|header| String::from_utf8_lossy(&header.value).into_owned())
+            .ok_or_else(|| HttpError::MissingSignatureHeader(name.to_string()))
+    }
+}
+
+/// Parses the base64-encoded signature out of a `Signature` header value of the form
+/// `keyId="...",algorithm="ed25519",headers="...",signature="<base64>"`.
+fn parse_signature(header: &str) -> Result<ed25519_dalek::Signature, HttpError> {
+    let encoded = header
+        .split(',')
+        .find_map(|field| field.trim().strip_prefix("signature=\""))
+        .and_then(|value| value.strip_suffix('"'))
+        .ok_or(HttpError::MalformedSignature)?;
+    let bytes = STANDARD
+        .decode(encoded)
+        .map_err(|_| HttpError::MalformedSignature)?;
+    ed25519_dalek::Signature::from_slice(&bytes).map_err(|_| HttpError::MalformedSignature)
+}
+
+#[cfg(with_reqwest)]
+impl TryFrom<Request> for reqwest::Request {
+    type Error = HttpError;
+
+    // Built directly, rather than through `reqwest::Client::request(..).build()`, since that
+    // would require a `reqwest::Client` (with its own connection pool) that `ReqwestClient::send`
+    // would otherwise have to construct and discard on every call instead of reusing its own.
+    fn try_from(request: Request) -> Result<Self, Self::Error> {
+        let url = url::Url::parse(&request.url)?;
+        let mut reqwest_request = reqwest::Request::new(request.method.into(), url);
+
+        for header in request.headers {
+            let name = reqwest::header::HeaderName::from_bytes(header.name.as_bytes())
+                .map_err(|error| HttpError::InvalidHeader(error.to_string()))?;
+            let value = reqwest::header::HeaderValue::from_bytes(&header.value)
+                .map_err(|error| HttpError::InvalidHeader(error.to_string()))?;
+            reqwest_request.headers_mut().append(name, value);
+        }
+
+        if !request.body.is_empty() {
+            *reqwest_request.body_mut() = Some(request.body.into());
+        }
+
+        Ok(reqwest_request)
+    }
+}
+
+/// A client capable of sending outbound HTTP [`Request`]s.
+///
+/// This is the abstraction boundary between application code and the underlying transport: the
+/// default implementation is [`ReqwestClient`], but tests can register a stub client that
+/// returns canned [`Response`]s, and the runtime can swap in a replay-based client so that
+/// oracle HTTP calls remain reproducible across validators.
+#[async_trait]
+pub trait HttpClient: Send + Sync {
+    /// Sends `request` and returns the resulting [`Response`].
+    async fn send(&self, request: Request) -> Result<Response, HttpError>;
+}
+
+/// The default [`HttpClient`], backed by `reqwest`.
+#[cfg(with_reqwest)]
+#[derive(Clone, Debug, Default)]
+pub struct ReqwestClient {
+    client: reqwest::Client,
+}
+
+#[cfg(with_reqwest)]
+#[async_trait]
+impl HttpClient for ReqwestClient {
+    async fn send(&self, request: Request) -> Result<Response, HttpError> {
+        let request = reqwest::Request::try_from(request)?;
+        let response = self.client.execute(request).await?;
+        Ok(Response::from_reqwest(response).await?)
+    }
+}
+
+/// Configuration for [`RetryClient`]'s exponential backoff.
+#[derive(Clone, Copy, Debug)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts made before giving up, including the first one.
+    pub max_attempts: u32,
+
+    /// The base delay `d` used to compute the backoff for attempt `n` as `d * 2^n`.
+    pub base_delay: Duration,
+
+    /// The maximum delay between attempts, before jitter is added.
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 4,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(10),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Returns the delay to wait before retrying for the `attempt`th time (starting at 0),
+    /// adding jitter of up to the capped delay.
+    fn delay_for_attempt(&self, attempt: u32) -> Duration {
+        let factor = 1u32.checked_shl(attempt).unwrap_or(u32::MAX);
+        let exponential = self.base_delay.saturating_mul(factor);
+        let capped = exponential.min(self.max_delay);
+        let jitter = capped.mul_f64(rand::thread_rng().gen::<f64>());
+        capped.saturating_add(jitter)
+    }
+}
+
+/// Wraps an [`HttpClient`], retrying requests that fail with a connection error or that come
+/// back with a 429/5xx response, using exponential backoff with jitter, bounded by a
+/// [`RetryPolicy`]. Every other, deterministic error is returned immediately.
+#[derive(Clone, Debug)]
+pub struct RetryClient<C> {
+    client: C,
+    policy: RetryPolicy,
+}
+
+impl<C> RetryClient<C> {
+    /// Wraps `client`, retrying failed requests according to `policy`.
+    pub fn new(client: C, policy: RetryPolicy) -> Self {
+        RetryClient { client, policy }
+    }
+
+    /// Returns whether `status` should be retried.
+    fn is_retryable_status(status: u16) -> bool {
+        status == 429 || (500..600).contains(&status)
+    }
+
+    /// Parses a `Retry-After` header containing a number of seconds, if present.
+    fn retry_after(response: &Response) -> Option<Duration> {
+        let (_, value) = response
+            .headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("retry-after"))?;
+        let seconds: u64 = std::str::from_utf8(value).ok()?.trim().parse().ok()?;
+        Some(Duration::from_secs(seconds))
+    }
+}
+
+#[async_trait]
+impl<C: HttpClient> HttpClient for RetryClient<C> {
+    async fn send(&self, request: Request) -> Result<Response, HttpError> {
+        let mut attempt = 0;
+        loop {
+            let result = self.client.send(request.clone()).await;
+            attempt += 1;
+
+            let retry_after = match &result {
+                Ok(response) if Self::is_retryable_status(response.status) => {
+                    Self::retry_after(response)
+                }
+                Ok(_) => return result,
+                Err(error) if error.is_retryable() => None,
+                Err(_) => return result,
+            };
+
+            if attempt >= self.policy.max_attempts {
+                return result;
+            }
+
+            let delay =
+                retry_after.unwrap_or_else(|| self.policy.delay_for_attempt(attempt - 1));
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod retry_tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    fn response(status: u16) -> Response {
+        Response {
+            status,
+            headers: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn is_retryable_status_matches_429_and_5xx_only() {
+        assert!(RetryClient::<()>::is_retryable_status(429));
+        assert!(RetryClient::<()>::is_retryable_status(500));
+        assert!(RetryClient::<()>::is_retryable_status(599));
+        assert!(!RetryClient::<()>::is_retryable_status(200));
+        assert!(!RetryClient::<()>::is_retryable_status(404));
+        assert!(!RetryClient::<()>::is_retryable_status(428));
+    }
+
+    #[test]
+    fn deterministic_errors_are_not_retryable() {
+        assert!(!HttpError::DigestMismatch.is_retryable());
+        assert!(!HttpError::MalformedSignature.is_retryable());
+        assert!(!HttpError::MissingSignatureHeader("host".to_string()).is_retryable());
+        assert!(!HttpError::BodyTooLarge(0).is_retryable());
+    }
+
+    #[test]
+    fn delay_for_attempt_is_within_jittered_bounds() {
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(10),
+            max_delay: Duration::from_millis(50),
+        };
+
+        for attempt in 0..10 {
+            let capped = policy.base_delay.saturating_mul(1 << attempt).min(policy.max_delay);
+            let delay = policy.delay_for_attempt(attempt);
+            assert!(delay >= capped, "attempt {attempt}: {delay:?} < {capped:?}");
+            assert!(delay <= capped * 2, "attempt {attempt}: {delay:?} > {capped:?} * 2");
+        }
+    }
+
+    struct StubClient {
+        responses: Mutex<Vec<Result<Response, HttpError>>>,
+    }
+
+    #[async_trait]
+    impl HttpClient for StubClient {
+        async fn send(&self, _request: Request) -> Result<Response, HttpError> {
+            self.responses.lock().unwrap().remove(0)
+        }
+    }
+
+    fn fast_policy() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(2),
+        }
+    }
+
+    #[tokio::test]
+    async fn retries_retryable_status_then_succeeds() {
+        let client = StubClient {
+            responses: Mutex::new(vec![Ok(response(503)), Ok(response(200))]),
+        };
+        let retry_client = RetryClient::new(client, fast_policy());
+
+        let result = retry_client
+            .send(Request::get("https://example.com").unwrap())
+            .await
+            .expect("second attempt should succeed");
+
+        assert_eq!(result.status, 200);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_deterministic_errors() {
+        let client = StubClient {
+            responses: Mutex::new(vec![Err(HttpError::DigestMismatch)]),
+        };
+        let retry_client = RetryClient::new(client, fast_policy());
+
+        let result = retry_client
+            .send(Request::get("https://example.com").unwrap())
+            .await;
+
+        assert!(matches!(result, Err(HttpError::DigestMismatch)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn first_retry_waits_delay_for_attempt_zero_not_one() {
+        // With no jitter upper bound overlap between `delay_for_attempt(0)` and
+        // `delay_for_attempt(1)` (a `max_delay` far above both keeps them uncapped, and each
+        // attempt doubles the delay, so jitter of up to 2x cannot make the ranges overlap), the
+        // elapsed wait tells us which one the call site actually used.
+        let policy = RetryPolicy {
+            max_attempts: 2,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(10),
+        };
+        let client = StubClient {
+            responses: Mutex::new(vec![Ok(response(503)), Ok(response(200))]),
+        };
+        let retry_client = RetryClient::new(client, policy);
+
+        let start = tokio::time::Instant::now();
+        retry_client
+            .send(Request::get("https://example.com").unwrap())
+            .await
+            .expect("second attempt should succeed");
+        let elapsed = start.elapsed();
+
+        // `delay_for_attempt(0)` is in `[base_delay, 2 * base_delay]`; `delay_for_attempt(1)`
+        // (the pre-fix, off-by-one value) would be in `[2 * base_delay, 4 * base_delay]`.
+        assert!(elapsed >= policy.base_delay, "elapsed {elapsed:?} too short");
+        assert!(
+            elapsed <= policy.base_delay * 2,
+            "elapsed {elapsed:?} suggests the old off-by-one"
+        );
+    }
+}
+
+#[cfg(test)]
+mod signature_tests {
+    use super::*;
+
+    fn keys() -> (SigningKey, VerifyingKey) {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let public_key = key.verifying_key();
+        (key, public_key)
+    }
+
+    #[test]
+    fn request_round_trips_through_sign_and_verify() {
+        let (key, public_key) = keys();
+        let request = Request::get("https://example.com/path")
+            .unwrap()
+            .header("host", "example.com")
+            .header("date", "Thu, 01 Jan 1970 00:00:00 GMT")
+            .signed(&key, "test-key")
+            .expect("signing should succeed");
+
+        request
+            .verify_signature(&public_key)
+            .expect("signature should verify");
+    }
+
+    #[test]
+    fn request_with_tampered_body_is_rejected() {
+        let (key, public_key) = keys();
+        let mut request = Request::get("https://example.com/path")
+            .unwrap()
+            .header("host", "example.com")
+            .header("date", "Thu, 01 Jan 1970 00:00:00 GMT")
+            .signed(&key, "test-key")
+            .expect("signing should succeed");
+
+        request.body = b"tampered".to_vec();
+
+        assert!(matches!(
+            request.verify_signature(&public_key),
+            Err(HttpError::DigestMismatch)
+        ));
+    }
+
+    #[test]
+    fn response_round_trips_through_sign_and_verify() {
+        let (key, public_key) = keys();
+        let response = Response {
+            status: 200,
+            headers: vec![("date".to_string(), b"Thu, 01 Jan 1970 00:00:00 GMT".to_vec())],
+            body: b"hello".to_vec(),
+        }
+        .signed(&key, "test-key")
+        .expect("signing should succeed");
+
+        response
+            .verify_signature(&public_key)
+            .expect("signature should verify");
+    }
+
+    #[test]
+    fn response_with_tampered_body_is_rejected() {
+        let (key, public_key) = keys();
+        let mut response = Response {
+            status: 200,
+            headers: vec![("date".to_string(), b"Thu, 01 Jan 1970 00:00:00 GMT".to_vec())],
+            body: b"hello".to_vec(),
+        }
+        .signed(&key, "test-key")
+        .expect("signing should succeed");
+
+        response.body = b"tampered".to_vec();
+
+        assert!(matches!(
+            response.verify_signature(&public_key),
+            Err(HttpError::DigestMismatch)
+        ));
+    }
+}
+
+#[cfg(all(test, with_reqwest))]
+mod body_limit_tests {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    /// Starts a server that replies to a single request with `head` (the status line and
+    /// headers, `\r\n\r\n`-terminated) followed by `chunks`, written and flushed one at a time so
+    /// the client observes them as separate stream items, then closes the connection. Returns the
+    /// URL to request.
+    async fn serve_once(head: &'static str, chunks: Vec<Vec<u8>>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = socket.read(&mut buf).await;
+            socket.write_all(head.as_bytes()).await.unwrap();
+            for chunk in chunks {
+                socket.write_all(&chunk).await.unwrap();
+                socket.flush().await.unwrap();
+            }
+            let _ = socket.shutdown().await;
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn declared_content_length_over_cap_is_rejected_without_reading_body() {
+        let url = serve_once(
+            "HTTP/1.1 200 OK\r\ncontent-length: 1000000\r\n\r\n",
+            vec![b"0123456789".to_vec()],
+        )
+        .await;
+
+        let response = reqwest::get(url).await.unwrap();
+        let result = Response::from_reqwest_with_limit(response, 10).await;
+
+        assert!(matches!(result, Err(HttpError::BodyTooLarge(10))));
+    }
+
+    #[tokio::test]
+    async fn body_without_content_length_exceeding_cap_is_rejected_via_streaming() {
+        let url = serve_once(
+            "HTTP/1.1 200 OK\r\nconnection: close\r\n\r\n",
+            vec![b"01234".to_vec(), b"56789".to_vec()],
+        )
+        .await;
+
+        let response = reqwest::get(url).await.unwrap();
+        let result = Response::from_reqwest_with_limit(response, 5).await;
+
+        assert!(matches!(result, Err(HttpError::BodyTooLarge(5))));
+    }
+
+    #[tokio::test]
+    async fn body_exactly_at_cap_succeeds() {
+        let url = serve_once(
+            "HTTP/1.1 200 OK\r\ncontent-length: 10\r\n\r\n",
+            vec![b"0123456789".to_vec()],
+        )
+        .await;
+
+        let response = reqwest::get(url).await.unwrap();
+        let result = Response::from_reqwest_with_limit(response, 10)
+            .await
+            .expect("body exactly at the cap should be accepted");
+
+        assert_eq!(result.body, b"0123456789");
+    }
+
+    #[tokio::test]
+    async fn response_stream_yields_chunks_then_ends() {
+        let url = serve_once(
+            "HTTP/1.1 200 OK\r\nconnection: close\r\n\r\n",
+            vec![b"abc".to_vec(), b"def".to_vec()],
+        )
+        .await;
+
+        let response = reqwest::get(url).await.unwrap();
+        let mut stream = ResponseStream::new(response);
+
+        let mut body = Vec::new();
+        while let Some(chunk) = stream.next_chunk().await {
+            body.extend(chunk.expect("chunk should be read successfully"));
+        }
+
+        assert_eq!(body, b"abcdef");
+    }
+
+    #[tokio::test]
+    async fn reqwest_client_sends_custom_headers_and_body_through_its_pooled_client() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 4096];
+            let read = socket.read(&mut buf).await.unwrap();
+            let received = String::from_utf8_lossy(&buf[..read]).into_owned();
+            let matched = received.contains("x-test: hello") && received.ends_with("ping");
+            let body = format!("matched:{matched}");
+            let head = format!("HTTP/1.1 200 OK\r\ncontent-length: {}\r\n\r\n", body.len());
+            socket.write_all(head.as_bytes()).await.unwrap();
+            socket.write_all(body.as_bytes()).await.unwrap();
+            let _ = socket.shutdown().await;
+        });
+
+        let client = ReqwestClient::default();
+        let request = Request::post(url)
+            .unwrap()
+            .header("x-test", "hello")
+            .body(b"ping".to_vec());
+
+        let response = client.send(request).await.expect("request should succeed");
+
+        assert_eq!(response.body, b"matched:true");
+    }
+}
+
+#[cfg(test)]
+mod http_client_tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    /// A stub [`HttpClient`] that returns a canned [`Response`], the kind of test double the
+    /// trait exists to let callers register in place of [`ReqwestClient`].
+    struct StubClient {
+        response: Response,
+    }
+
+    #[async_trait]
+    impl HttpClient for StubClient {
+        async fn send(&self, _request: Request) -> Result<Response, HttpError> {
+            Ok(self.response.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn http_client_is_usable_as_arc_dyn_http_client() {
+        let client: Arc<dyn HttpClient> = Arc::new(StubClient {
+            response: Response {
+                status: 200,
+                headers: Vec::new(),
+                body: b"canned".to_vec(),
+            },
+        });
+
+        let response = client
+            .send(Request::get("https://example.com").unwrap())
+            .await
+            .expect("stub client should not fail");
+
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, b"canned");
+    }
 }
\ No newline at end of file
@@ -5,9 +5,12 @@
 
 mod state;
 
+use std::collections::HashMap;
+
 use self::state::Counter;
-use async_graphql::{EmptyMutation, EmptySubscription, Schema};
+use async_graphql::{EmptyMutation, Schema, Subscription};
 use async_trait::async_trait;
+use futures::stream::{self, Stream, StreamExt};
 use linera_sdk::{QueryContext, Service, SimpleStateStorage};
 use thiserror::Error;
 
@@ -22,6 +25,23 @@ impl DummyObject {
     }
 }
 
+/// Subscriptions over the counter's value.
+///
+/// The `value` stream yields the counter's value once, as it is when the subscription is made,
+/// and then ends; it yields no later updates. `query_application` drives it through
+/// `Schema::execute_stream` and returns only the first item, since its `Vec<u8>` return type is
+/// one-shot.
+struct CounterSubscription {
+    value: u128,
+}
+
+#[Subscription]
+impl CounterSubscription {
+    async fn value(&self) -> impl Stream<Item = u128> {
+        stream::once(futures::future::ready(self.value))
+    }
+}
+
 #[async_trait]
 impl Service for Counter {
     type Error = Error;
@@ -32,20 +52,182 @@ impl Service for Counter {
         _context: &QueryContext,
         argument: &[u8],
     ) -> Result<Vec<u8>, Self::Error> {
-        let graphql_request: async_graphql::Request = serde_json::from_slice(argument).unwrap();
+        let graphql_request = parse_graphql_request(argument)?;
         let dummy = DummyObject;
-        let schema = Schema::build(dummy, EmptyMutation, EmptySubscription).finish();
-        let res = schema.execute(graphql_request).await;
+        let subscription = CounterSubscription { value: self.value };
+        let schema = Schema::build(dummy, EmptyMutation, subscription).finish();
+
+        // `execute` only drives query/mutation operations; subscriptions must go through
+        // `execute_stream`, so route everything there and take the first (and, for
+        // query/mutation, only) item it yields.
+        let res = schema
+            .execute_stream(graphql_request)
+            .next()
+            .await
+            .unwrap_or_else(|| {
+                async_graphql::Response::from_errors(vec![async_graphql::ServerError::new(
+                    "the GraphQL schema produced no response",
+                    None,
+                )])
+            });
         Ok(serde_json::to_vec(&res).unwrap())
     }
 }
 
+/// The prefix that precedes a `multipart/form-data` argument, encoding the content type that a
+/// real HTTP entry point would instead carry as a header. Arguments without this prefix are
+/// parsed as a plain JSON [`async_graphql::Request`], as before.
+const MULTIPART_PREFIX: &[u8] = b"Content-Type: multipart/form-data";
+
+/// Parses `argument` into an [`async_graphql::Request`], supporting both a plain JSON body and a
+/// `multipart/form-data` body carrying file uploads.
+fn parse_graphql_request(argument: &[u8]) -> Result<async_graphql::Request, Error> {
+    match argument.strip_prefix(MULTIPART_PREFIX) {
+        Some(rest) => receive_multipart(rest),
+        None => serde_json::from_slice(argument)
+            .map_err(|error| Error::InvalidRequest(error.to_string())),
+    }
+}
+
+/// Decodes a `multipart/form-data` GraphQL request following the [`operations`/`map`
+/// convention](https://github.com/jaydenseric/graphql-multipart-request-spec): an `operations`
+/// part holding the GraphQL request as JSON (with `null` placeholders for uploads), a `map` part
+/// mapping part names to the variable paths they fill in, and one further part per upload.
+fn receive_multipart(rest: &[u8]) -> Result<async_graphql::Request, Error> {
+    let (header_end, separator_len) = find_header_separator(rest)
+        .ok_or_else(|| Error::InvalidRequest("missing multipart header terminator".to_string()))?;
+    let header = std::str::from_utf8(&rest[..header_end])
+        .map_err(|error| Error::InvalidRequest(error.to_string()))?;
+    let boundary = header
+        .split(';')
+        .find_map(|field| field.trim().strip_prefix("boundary="))
+        .map(|boundary| boundary.trim_matches('"'))
+        .ok_or_else(|| Error::InvalidRequest("missing multipart boundary".to_string()))?;
+    let body = &rest[header_end + separator_len..];
+
+    let parts = split_multipart_parts(body, boundary)?;
+
+    let operations = parts
+        .get("operations")
+        .ok_or_else(|| Error::MissingMultipartPart("operations".to_string()))?;
+    let mut request: async_graphql::Request = serde_json::from_slice(operations)
+        .map_err(|error| Error::InvalidRequest(error.to_string()))?;
+
+    let map = parts
+        .get("map")
+        .ok_or_else(|| Error::MissingMultipartPart("map".to_string()))?;
+    let map: HashMap<String, Vec<String>> =
+        serde_json::from_slice(map).map_err(|error| Error::InvalidRequest(error.to_string()))?;
+
+    for (part_name, variable_paths) in map {
+        let content = parts
+            .get(part_name.as_str())
+            .ok_or_else(|| Error::MissingMultipartPart(part_name.clone()))?;
+        let upload = upload_value_from_bytes(part_name.clone(), content)?;
+        for path in variable_paths {
+            let upload = upload
+                .try_clone()
+                .map_err(|error| Error::InvalidRequest(error.to_string()))?;
+            request = request.set_upload(&path, upload);
+        }
+    }
+
+    Ok(request)
+}
+
+/// Builds an [`async_graphql::UploadValue`] for `content`, spooling it to a temporary file, as
+/// `async_graphql`'s own multipart handling does for parts read directly off the wire.
+fn upload_value_from_bytes(
+    filename: String,
+    content: &[u8],
+) -> Result<async_graphql::UploadValue, Error> {
+    use std::io::{Seek, SeekFrom, Write};
+
+    let mut file = tempfile::tempfile().map_err(|error| Error::InvalidRequest(error.to_string()))?;
+    file.write_all(content)
+        .and_then(|()| file.seek(SeekFrom::Start(0)))
+        .map_err(|error| Error::InvalidRequest(error.to_string()))?;
+
+    Ok(async_graphql::UploadValue {
+        filename,
+        content_type: None,
+        content: file,
+    })
+}
+
+/// Splits a `multipart/form-data` body delimited by `boundary` into a map of part name to part
+/// body, ignoring any headers other than `Content-Disposition`'s `name`.
+fn split_multipart_parts<'a>(
+    body: &'a [u8],
+    boundary: &str,
+) -> Result<HashMap<&'a str, &'a [u8]>, Error> {
+    let delimiter = format!("--{boundary}");
+    let mut parts = HashMap::new();
+    let mut remainder = body;
+    while let Some(start) = find_subslice(remainder, delimiter.as_bytes()) {
+        remainder = &remainder[start + delimiter.len()..];
+        if remainder.starts_with(b"--") {
+            break;
+        }
+        let Some(next) = find_subslice(remainder, delimiter.as_bytes()) else {
+            break;
+        };
+        let part = &remainder[..next];
+        let Some((header_end, separator_len)) = find_header_separator(part) else {
+            continue;
+        };
+        // `str::from_utf8` (rather than `String::from_utf8_lossy`) keeps the returned `&str`
+        // borrowing from `part` itself instead of from a temporary `Cow`, so `name` below can
+        // outlive this loop iteration.
+        let Ok(headers) = std::str::from_utf8(&part[..header_end]) else {
+            continue;
+        };
+        let Some(name) = headers
+            .split("; ")
+            .chain(headers.split(';'))
+            .find_map(|field| field.trim().strip_prefix("name=\""))
+            .and_then(|value| value.strip_suffix('"'))
+        else {
+            continue;
+        };
+        let content = &part[header_end + separator_len..];
+        let content = content.strip_suffix(b"\r\n").unwrap_or(content);
+        parts.insert(name, content);
+    }
+
+    Ok(parts)
+}
+
+/// Returns the index of the first occurrence of `needle` in `haystack`, if any.
+fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Locates the blank line separating headers from body in `data`, preferring the CRLF line
+/// endings real multipart bodies use and falling back to bare `\n\n`. Returns the index the
+/// headers end at and the length of the separator found there.
+fn find_header_separator(data: &[u8]) -> Option<(usize, usize)> {
+    if let Some(index) = find_subslice(data, b"\r\n\r\n") {
+        Some((index, 4))
+    } else {
+        find_subslice(data, b"\n\n").map(|index| (index, 2))
+    }
+}
+
 /// An error that can occur during the contract execution.
 #[derive(Debug, Error, Eq, PartialEq)]
 pub enum Error {
-    /// Invalid query argument; Counter application only supports a single (empty) query.
-    #[error("Invalid query argument; Counter application only supports a single (empty) query")]
-    InvalidQuery,
+    /// The argument could not be parsed as a GraphQL request, or an uploaded part of a
+    /// `multipart/form-data` request could not be stored in a temporary file.
+    #[error("invalid GraphQL request: {0}")]
+    InvalidRequest(String),
+
+    /// A `multipart/form-data` request's `map` referenced a part that was not included in the
+    /// body.
+    #[error("multipart request is missing part `{0}`")]
+    MissingMultipartPart(String),
 }
 
 #[cfg(test)]
@@ -71,6 +253,26 @@ mod tests {
         assert_eq!(result, Ok(expected_response));
     }
 
+    #[webassembly_test]
+    fn subscription() {
+        let value = 7_u128;
+        let counter = Counter { value };
+
+        let request = async_graphql::Request::new("subscription { value }");
+        let argument = serde_json::to_vec(&request).expect("request should serialize");
+
+        let result = counter
+            .query_application(&dummy_query_context(), &argument)
+            .now_or_never()
+            .expect("Query should not await anything")
+            .expect("subscription query should succeed");
+
+        let response: async_graphql::Response =
+            serde_json::from_slice(&result).expect("response should deserialize");
+
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+    }
+
     #[webassembly_test]
     fn invalid_query() {
         let value = 4_u128;
@@ -82,7 +284,7 @@ mod tests {
             .now_or_never()
             .expect("Query should not await anything");
 
-        assert_eq!(result, Err(Error::InvalidQuery));
+        assert!(matches!(result, Err(Error::InvalidRequest(_))));
     }
 
     fn dummy_query_context() -> QueryContext {
@@ -90,4 +292,47 @@ mod tests {
             chain_id: ChainId([0; 8].into()),
         }
     }
+
+    #[test]
+    fn receive_multipart_accepts_quoted_boundary_and_crlf() {
+        let boundary = "abc123";
+        let multipart_body = format!(
+            "--{boundary}\r\n\
+             Content-Disposition: form-data; name=\"operations\"\r\n\r\n\
+             {{\"query\":\"{{ hello }}\"}}\r\n\
+             --{boundary}\r\n\
+             Content-Disposition: form-data; name=\"map\"\r\n\r\n\
+             {{}}\r\n\
+             --{boundary}--\r\n"
+        );
+        let argument = format!(
+            "Content-Type: multipart/form-data; boundary=\"{boundary}\"\r\n\r\n{multipart_body}"
+        );
+
+        let request = super::parse_graphql_request(argument.as_bytes())
+            .expect("quoted-boundary, CRLF multipart request should parse");
+
+        assert_eq!(request.query, "{ hello }");
+    }
+
+    #[test]
+    fn receive_multipart_reports_missing_part() {
+        let boundary = "abc123";
+        let multipart_body = format!(
+            "--{boundary}\r\n\
+             Content-Disposition: form-data; name=\"operations\"\r\n\r\n\
+             {{\"query\":\"{{ hello }}\"}}\r\n\
+             --{boundary}\r\n\
+             Content-Disposition: form-data; name=\"map\"\r\n\r\n\
+             {{\"0\":[\"variables.file\"]}}\r\n\
+             --{boundary}--\r\n"
+        );
+        let argument = format!(
+            "Content-Type: multipart/form-data; boundary={boundary}\r\n\r\n{multipart_body}"
+        );
+
+        let result = super::parse_graphql_request(argument.as_bytes());
+
+        assert_eq!(result.unwrap_err(), Error::MissingMultipartPart("0".to_string()));
+    }
 }